@@ -16,19 +16,77 @@
 #pragma version(1)
 #pragma rs java_package_name(android.view.cts.surfacevalidator)
 #pragma rs reduce(countBlackishPixels) accumulator(countBlackishPixelsAccum) combiner(countBlackishPixelsCombiner)
+#pragma rs reduce(countMatchingPixels) accumulator(countMatchingPixelsAccum) combiner(countMatchingPixelsCombiner)
+#pragma rs reduce(countDarkPixelsByLuma) accumulator(countDarkPixelsByLumaAccum) combiner(countDarkPixelsByLumaCombiner)
+#pragma rs reduce(accumHistogram) accumulator(accumHistogramAccum) combiner(accumHistogramCombiner)
 
 uchar THRESHOLD;
 int BOUNDS[4];
 
+// Additional regions of interest, each encoded as 4 ints (x0, y0, x1, y1) like BOUNDS and packed
+// into an rs_allocation so the rect count isn't bounded by a compile-time array size. A pixel is
+// in bounds if it falls in BOUNDS or any RECTS entry (when NUM_RECTS is set), and is vetoed if it
+// falls in any EXCLUDE_RECTS entry. NUM_RECTS/NUM_EXCLUDE_RECTS default to 0, so existing callers
+// that only set BOUNDS keep working unchanged.
+int NUM_RECTS;
+rs_allocation RECTS;
+int NUM_EXCLUDE_RECTS;
+rs_allocation EXCLUDE_RECTS;
+
+uchar4 TARGET_COLOR;
+uchar4 TOLERANCE;
+
+// CHANNEL_ORDER enum values, matching the layouts surfaces can be captured in.
+static const int CHANNEL_ORDER_RGBA = 0;
+static const int CHANNEL_ORDER_ARGB = 1;
+static const int CHANNEL_ORDER_BGRA = 2;
+
+int CHANNEL_ORDER;
+
+// Remaps pixel into canonical R,G,B,A order based on CHANNEL_ORDER, so accumulators can always
+// read pixel.r/.g/.b regardless of how the source surface was captured.
+static uchar4 toCanonicalRgba(uchar4 pixel) {
+    if (CHANNEL_ORDER == CHANNEL_ORDER_ARGB) {
+        return (uchar4){pixel.g, pixel.b, pixel.a, pixel.r};
+    }
+    if (CHANNEL_ORDER == CHANNEL_ORDER_BGRA) {
+        return (uchar4){pixel.b, pixel.g, pixel.r, pixel.a};
+    }
+    return pixel;
+}
+
+static bool rectContains(rs_allocation rects, int index, uint32_t x, uint32_t y) {
+    int base = index * 4;
+    int x0 = rsGetElementAt_int(rects, base);
+    int y0 = rsGetElementAt_int(rects, base + 1);
+    int x1 = rsGetElementAt_int(rects, base + 2);
+    int y1 = rsGetElementAt_int(rects, base + 3);
+    return x >= x0 && x < x1 && y >= y0 && y < y1;
+}
+
+static bool isInBounds(uint32_t x, uint32_t y) {
+    bool included = (x >= BOUNDS[0] && x < BOUNDS[2] && y >= BOUNDS[1] && y < BOUNDS[3]);
+    for (int i = 0; !included && i < NUM_RECTS; i++) {
+        included = rectContains(RECTS, i, x, y);
+    }
+    if (!included) {
+        return false;
+    }
+    for (int i = 0; i < NUM_EXCLUDE_RECTS; i++) {
+        if (rectContains(EXCLUDE_RECTS, i, x, y)) {
+            return false;
+        }
+    }
+    return true;
+}
+
 static void countBlackishPixelsAccum(int *accum, uchar4 pixel, uint32_t x, uint32_t y) {
+    pixel = toCanonicalRgba(pixel);
 
     if (pixel.r < THRESHOLD
             && pixel.g < THRESHOLD
             && pixel.b < THRESHOLD
-            && x >= BOUNDS[0]
-            && x < BOUNDS[2]
-            && y >= BOUNDS[1]
-            && y < BOUNDS[3]) {
+            && isInBounds(x, y)) {
         *accum += 1;
     }
 }
@@ -36,3 +94,54 @@ static void countBlackishPixelsAccum(int *accum, uchar4 pixel, uint32_t x, uint3
 static void countBlackishPixelsCombiner(int *accum, const int *other){
     *accum += *other;
 }
+
+static void countMatchingPixelsAccum(int *accum, uchar4 pixel, uint32_t x, uint32_t y) {
+    pixel = toCanonicalRgba(pixel);
+
+    if (abs(pixel.r - TARGET_COLOR.r) <= TOLERANCE.r
+            && abs(pixel.g - TARGET_COLOR.g) <= TOLERANCE.g
+            && abs(pixel.b - TARGET_COLOR.b) <= TOLERANCE.b
+            && isInBounds(x, y)) {
+        *accum += 1;
+    }
+}
+
+static void countMatchingPixelsCombiner(int *accum, const int *other){
+    *accum += *other;
+}
+
+// Integer BT.601 luma approximation: (77*R + 150*G + 29*B) >> 8, in the range 0..255.
+static uchar luma(uchar4 pixel) {
+    return (77 * pixel.r + 150 * pixel.g + 29 * pixel.b) >> 8;
+}
+
+static void countDarkPixelsByLumaAccum(int *accum, uchar4 pixel, uint32_t x, uint32_t y) {
+    pixel = toCanonicalRgba(pixel);
+
+    if (luma(pixel) < THRESHOLD && isInBounds(x, y)) {
+        *accum += 1;
+    }
+}
+
+static void countDarkPixelsByLumaCombiner(int *accum, const int *other){
+    *accum += *other;
+}
+
+// Per-luma-bucket pixel counts, for callers that need a distribution rather than a single count.
+typedef struct {
+    int bins[256];
+} Histogram;
+
+static void accumHistogramAccum(Histogram *accum, uchar4 pixel, uint32_t x, uint32_t y) {
+    pixel = toCanonicalRgba(pixel);
+
+    if (isInBounds(x, y)) {
+        accum->bins[luma(pixel)] += 1;
+    }
+}
+
+static void accumHistogramCombiner(Histogram *accum, const Histogram *other) {
+    for (int i = 0; i < 256; i++) {
+        accum->bins[i] += other->bins[i];
+    }
+}